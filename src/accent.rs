@@ -0,0 +1,64 @@
+use palette::{FromColor, Hsv, Srgb};
+use serde::{Deserialize, Serialize};
+
+/// A user-chosen accent color, stored as hue/saturation/brightness so lighter and
+/// darker variants can be derived by nudging brightness alone.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Accent {
+    /// Hue in degrees, 0-360.
+    pub(crate) hue: f32,
+    /// Saturation, 0-1.
+    pub(crate) saturation: f32,
+    /// Brightness (value), 0-1.
+    pub(crate) brightness: f32,
+}
+
+impl Default for Accent {
+    fn default() -> Self {
+        // A blue close to egui's built-in selection color.
+        Self {
+            hue: 210.0,
+            saturation: 0.7,
+            brightness: 0.8,
+        }
+    }
+}
+
+const BRIGHTNESS_STEP: f32 = 0.15;
+
+impl Accent {
+    /// The accent at its stored brightness.
+    pub(crate) fn to_color32(self) -> egui::Color32 {
+        hsv_to_color32(self.hue, self.saturation, self.brightness)
+    }
+
+    /// The same hue/saturation nudged toward white, for hover states.
+    pub(crate) fn lighter(self) -> egui::Color32 {
+        hsv_to_color32(
+            self.hue,
+            self.saturation,
+            (self.brightness + BRIGHTNESS_STEP).min(1.0),
+        )
+    }
+
+    /// The same hue/saturation nudged toward black, for active/pressed states.
+    pub(crate) fn darker(self) -> egui::Color32 {
+        hsv_to_color32(
+            self.hue,
+            self.saturation,
+            (self.brightness - BRIGHTNESS_STEP).max(0.0),
+        )
+    }
+}
+
+/// Convert an HSV color (H in degrees, S/V in [0, 1]) to an `egui::Color32` via
+/// `palette`'s HSV-to-sRGB conversion.
+fn hsv_to_color32(hue: f32, saturation: f32, brightness: f32) -> egui::Color32 {
+    let srgb = Srgb::from_color(Hsv::new(hue, saturation, brightness));
+    let (r, g, b) = srgb.into_components();
+    egui::Color32::from_rgb(
+        (r * 255.0).round() as u8,
+        (g * 255.0).round() as u8,
+        (b * 255.0).round() as u8,
+    )
+}
@@ -0,0 +1,44 @@
+use fontdb::{Database, Family, Query};
+use std::sync::Arc;
+
+/// Enumerates the system's installed fonts and resolves a chosen family's bytes
+/// for use in an `egui::FontDefinitions`.
+pub(crate) struct FontCatalog {
+    db: Database,
+    family_names: Vec<String>,
+}
+
+impl FontCatalog {
+    /// Scan the system for installed fonts.
+    pub(crate) fn load() -> Self {
+        let mut db = Database::new();
+        db.load_system_fonts();
+
+        let mut family_names: Vec<String> = db
+            .faces()
+            .flat_map(|face| face.families.iter().map(|(name, _)| name.clone()))
+            .collect();
+        family_names.sort();
+        family_names.dedup();
+
+        Self { db, family_names }
+    }
+
+    /// The distinct family names available, sorted for display in a picker widget.
+    /// Scanned once at startup, so this is cheap to call every frame.
+    pub(crate) fn family_names(&self) -> &[String] {
+        &self.family_names
+    }
+
+    /// Resolve a family name to its face bytes, if a matching font is installed.
+    pub(crate) fn resolve(&self, family: &str) -> Option<Arc<Vec<u8>>> {
+        let query = Query {
+            families: &[Family::Name(family)],
+            ..Query::default()
+        };
+        let id = self.db.query(&query)?;
+        self.db
+            .with_face_data(id, |data, _face_index| data.to_vec())
+            .map(Arc::new)
+    }
+}
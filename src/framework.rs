@@ -1,31 +1,78 @@
+use crate::accent::Accent;
+use crate::fonts::FontCatalog;
 use crate::gpu::Gpu;
 use crate::gui::Gui;
+use crate::settings::{self, Settings};
+use crate::theme::{ThemeController, ThemePreference};
 use egui::ClippedMesh;
 use egui_wgpu_backend::{RenderPass, ScreenDescriptor};
 use egui_winit_platform::{Platform, PlatformDescriptor};
 use std::borrow::Cow;
-use std::time::Instant;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use winit::dpi::PhysicalSize;
 use winit::window::Theme;
 
+/// What the event loop should do once a frame is done, derived from egui's
+/// `needs_repaint` signal so the window can go idle instead of redrawing forever.
+///
+/// `egui_winit_platform`'s `Output` only ever tells us "repaint now" or nothing, with
+/// no timed-repaint hint to drive a `ControlFlow::WaitUntil`, so there are just the
+/// two states.
+pub(crate) enum Redraw {
+    /// egui has pending work (e.g. an animation); redraw right away.
+    Immediate,
+    /// Fully idle; don't redraw until a new input event arrives.
+    Wait,
+}
+
+/// Bridges appearance settings between `Framework` and `Gui` for one frame:
+/// `Framework` fills in the current values before drawing, `Gui` writes back
+/// whatever the user changed, and `Framework` applies the difference afterward.
+pub(crate) struct AppearanceState<'a> {
+    /// The user's theme preference.
+    pub(crate) theme_preference: ThemePreference,
+    /// System font families available for the picker, scanned once at startup.
+    pub(crate) available_fonts: &'a [String],
+    /// The currently selected system font family, if any.
+    pub(crate) font_family: Option<String>,
+    /// The current accent color.
+    pub(crate) accent: Accent,
+}
+
 /// Manages all state required for rendering egui.
 pub(crate) struct Framework {
     // State for egui.
     start_time: Instant,
     platform: Platform,
+    scale_factor: f64,
     screen_descriptor: ScreenDescriptor,
     rpass: RenderPass,
     paint_jobs: Vec<ClippedMesh>,
-    theme: Option<Theme>,
+    theme: ThemeController,
+    accent: Accent,
+    style_dirty: bool,
+    fonts: FontCatalog,
+    custom_font: Option<(String, Arc<Vec<u8>>)>,
+    fonts_dirty: bool,
+    settings: Settings,
+    /// Whether `settings` has changes not yet flushed to disk. Lets accent-slider
+    /// dragging update live state every frame without a blocking write on every tick.
+    settings_dirty: bool,
+    last_settings_save: Instant,
     gui: Gui,
 }
 
+/// Minimum time between persisted-settings writes while `settings_dirty` is set, so
+/// dragging a slider doesn't do a blocking file write on every `.changed()` tick.
+const SETTINGS_SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
 impl Framework {
     /// Create a framework for egui.
     pub(crate) fn new(
         size: PhysicalSize<u32>,
         scale_factor: f64,
-        theme: Theme,
+        mut settings: Settings,
         gui: Gui,
         gpu: &Gpu,
     ) -> Self {
@@ -46,13 +93,43 @@ impl Framework {
 
         install_fonts(&platform.context());
 
+        let theme = ThemeController::new(settings.theme);
+        let accent = settings.accent;
+        let fonts = FontCatalog::load();
+        let custom_font = settings
+            .font_family
+            .as_deref()
+            .and_then(|family| fonts.resolve(family).map(|bytes| (family.to_owned(), bytes)));
+
+        if custom_font.is_none() && settings.font_family.is_some() {
+            // The persisted family isn't installed on this machine (e.g. the config
+            // was copied from another computer). Drop it instead of leaving a ghost
+            // selection that `font_family()` would report as active but that's not
+            // actually applied.
+            log::warn!(
+                "persisted font family {:?} not found, clearing selection",
+                settings.font_family
+            );
+            settings.font_family = None;
+            settings::save(&settings);
+        }
+
         Self {
             start_time: Instant::now(),
             platform,
+            scale_factor,
             screen_descriptor,
             rpass,
             paint_jobs: Vec::new(),
-            theme: Some(theme),
+            theme,
+            accent,
+            style_dirty: false,
+            fonts,
+            custom_font,
+            fonts_dirty: false,
+            settings,
+            settings_dirty: false,
+            last_settings_save: Instant::now(),
             gui,
         }
     }
@@ -68,13 +145,40 @@ impl Framework {
         self.screen_descriptor.physical_height = height;
     }
 
-    /// Update scaling factor.
-    pub(crate) fn scale_factor(&mut self, scale_factor: f64) {
+    /// Update the scale factor, propagating it to both egui's input mapping and the
+    /// render `ScreenDescriptor` so they can't drift out of sync. This is the single
+    /// authoritative path for scale-factor changes; `new`'s `scale_factor` param and
+    /// this method are the only places it's ever set.
+    pub(crate) fn set_scale_factor(&mut self, scale_factor: f64) {
+        if (self.scale_factor - scale_factor).abs() < f64::EPSILON {
+            return;
+        }
+        self.scale_factor = scale_factor;
         self.screen_descriptor.scale_factor = scale_factor as f32;
+
+        // `egui_winit_platform`'s `Platform` bakes the scale factor in at
+        // construction time and exposes no setter, so rebuild it to keep egui's
+        // pointer-position mapping correct. This replaces the whole `egui::Context`,
+        // which otherwise wipes the font atlas and all remembered UI state (window
+        // positions, open/closed state, scroll/drag state), so both are carried over
+        // explicitly. Style and fonts are then marked dirty to reapply theme/accent
+        // and the custom font on top of that baseline next frame.
+        let memory = self.platform.context().memory().clone();
+        self.platform = Platform::new(PlatformDescriptor {
+            physical_width: self.screen_descriptor.physical_width,
+            physical_height: self.screen_descriptor.physical_height,
+            scale_factor,
+            ..PlatformDescriptor::default()
+        });
+        *self.platform.context().memory() = memory;
+        install_fonts(&self.platform.context());
+        self.style_dirty = true;
+        self.fonts_dirty = true;
     }
 
-    /// Prepare egui.
-    pub(crate) fn prepare(&mut self) {
+    /// Prepare egui. Returns what the event loop should do next so it can idle
+    /// instead of redrawing continuously.
+    pub(crate) fn prepare(&mut self) -> Redraw {
         self.platform
             .update_time(self.start_time.elapsed().as_secs_f64());
 
@@ -83,13 +187,40 @@ impl Framework {
 
         // Draw the application GUI.
         let ctx = self.platform.context();
-        self.update_theme(&ctx);
-        self.gui.ui(&ctx);
+        self.apply_style(&ctx);
+        if self.fonts_dirty {
+            self.fonts_dirty = false;
+            self.rebuild_fonts(&ctx);
+        }
+        let mut appearance = AppearanceState {
+            theme_preference: self.theme.preference(),
+            available_fonts: self.fonts.family_names(),
+            font_family: self.settings.font_family.clone(),
+            accent: self.accent,
+        };
+        self.gui.ui(&ctx, &mut appearance);
+        if appearance.theme_preference != self.theme.preference() {
+            self.set_theme_preference(appearance.theme_preference);
+        }
+        let chosen_font = appearance.font_family;
+        if chosen_font != self.settings.font_family {
+            self.set_font_family(chosen_font.as_deref());
+        }
+        if appearance.accent != self.accent {
+            self.set_accent(appearance.accent);
+        }
 
         // End the egui frame and create all paint jobs to prepare for rendering.
-        // TODO: Handle output.needs_repaint to avoid game-mode continuous redraws.
-        let (_output, paint_commands) = self.platform.end_frame();
+        let (output, paint_commands) = self.platform.end_frame();
         self.paint_jobs = self.platform.context().tessellate(paint_commands);
+
+        let settings_flush_pending = self.flush_settings();
+
+        if output.needs_repaint || settings_flush_pending {
+            Redraw::Immediate
+        } else {
+            Redraw::Wait
+        }
     }
 
     /// Render egui.
@@ -120,48 +251,154 @@ impl Framework {
         );
     }
 
-    /// Call this when the system theme changes.
-    pub(crate) fn change_theme(&mut self, theme: Theme) {
-        self.theme = Some(theme);
+    /// Call this when winit reports the OS theme changed. Only takes effect while the
+    /// user preference is `ThemePreference::System`.
+    pub(crate) fn change_theme(&mut self, os_theme: Theme) {
+        self.theme.refresh_system(os_theme);
     }
 
-    /// Configure the theme based on system settings.
-    fn update_theme(&mut self, ctx: &egui::CtxRef) {
-        if let Some(theme) = self.theme.take() {
-            // The default light theme has grey fonts. We want solid black.
-            let style = egui::Style {
-                visuals: match theme {
-                    Theme::Dark => egui::Visuals::dark(),
-                    Theme::Light => {
-                        let mut visuals = egui::Visuals::light();
+    /// Let the user override the auto-detected theme at runtime.
+    pub(crate) fn set_theme_preference(&mut self, preference: ThemePreference) {
+        self.theme.set_preference(preference);
+        self.settings.theme = preference;
+        settings::save(&self.settings);
+    }
 
-                        visuals.widgets.noninteractive.fg_stroke.color = egui::Color32::BLACK;
-                        visuals.widgets.inactive.fg_stroke.color = egui::Color32::BLACK;
+    pub(crate) fn theme_preference(&self) -> ThemePreference {
+        self.theme.preference()
+    }
 
-                        visuals
-                    }
-                },
-                ..egui::Style::default()
-            };
-            ctx.set_style(style);
+    pub(crate) fn accent(&self) -> Accent {
+        self.accent
+    }
+
+    /// Let the user override the accent color used to tint selection, hovered/active
+    /// widgets, and hyperlinks. Persistence is debounced (see `flush_settings`) since
+    /// this is called on every `.changed()` tick while a slider is being dragged.
+    pub(crate) fn set_accent(&mut self, accent: Accent) {
+        self.accent = accent;
+        self.style_dirty = true;
+        self.settings.accent = accent;
+        self.settings_dirty = true;
+    }
 
-            let mut fonts = ctx.fonts().definitions().clone();
-            if let Some(font) = fonts
+    /// The system font families available for the font picker.
+    pub(crate) fn available_fonts(&self) -> &[String] {
+        self.fonts.family_names()
+    }
+
+    /// The currently selected system font family, if the user picked one.
+    pub(crate) fn font_family(&self) -> Option<&str> {
+        self.settings.font_family.as_deref()
+    }
+
+    /// Let the user replace the proportional font with a system family, or pass
+    /// `None` to fall back to the embedded Ubuntu font. Does nothing if a given
+    /// family can't be resolved through `fontdb`.
+    pub(crate) fn set_font_family(&mut self, family: Option<&str>) {
+        let Some(family) = family else {
+            self.custom_font = None;
+            self.fonts_dirty = true;
+            self.settings.font_family = None;
+            settings::save(&self.settings);
+            return;
+        };
+        let Some(bytes) = self.fonts.resolve(family) else {
+            return;
+        };
+        self.custom_font = Some((family.to_owned(), bytes));
+        self.fonts_dirty = true;
+        self.settings.font_family = Some(family.to_owned());
+        settings::save(&self.settings);
+    }
+
+    /// Write pending settings to disk, but no more often than `SETTINGS_SAVE_DEBOUNCE`.
+    /// Returns whether a write is still outstanding, so `prepare` can keep scheduling
+    /// frames until the debounced save actually happens.
+    fn flush_settings(&mut self) -> bool {
+        if !self.settings_dirty {
+            return false;
+        }
+        if self.last_settings_save.elapsed() < SETTINGS_SAVE_DEBOUNCE {
+            return true;
+        }
+        settings::save(&self.settings);
+        self.settings_dirty = false;
+        self.last_settings_save = Instant::now();
+        false
+    }
+
+    /// Restyle egui based on the resolved theme and accent color, if either changed.
+    fn apply_style(&mut self, ctx: &egui::CtxRef) {
+        let theme_changed = self.theme.take_dirty();
+        if !theme_changed && !self.style_dirty {
+            return;
+        }
+        self.style_dirty = false;
+
+        let theme = self.theme.resolved();
+
+        // The default light theme has grey fonts. We want solid black.
+        let mut visuals = match theme {
+            Theme::Dark => egui::Visuals::dark(),
+            Theme::Light => {
+                let mut visuals = egui::Visuals::light();
+
+                visuals.widgets.noninteractive.fg_stroke.color = egui::Color32::BLACK;
+                visuals.widgets.inactive.fg_stroke.color = egui::Color32::BLACK;
+
+                visuals
+            }
+        };
+        apply_accent(&mut visuals, self.accent);
+
+        ctx.set_style(egui::Style {
+            visuals,
+            ..egui::Style::default()
+        });
+        self.rebuild_fonts(ctx);
+    }
+
+    /// Rebuild the font definitions from the embedded fonts plus the current theme's
+    /// preferred weight or the user's chosen system family, then push them to egui.
+    fn rebuild_fonts(&self, ctx: &egui::CtxRef) {
+        let mut fonts = ctx.fonts().definitions().clone();
+
+        if let Some((name, bytes)) = &self.custom_font {
+            fonts
+                .font_data
+                .insert(name.clone(), Cow::Owned(bytes.as_ref().clone()));
+            if let Some(family) = fonts
                 .fonts_for_family
                 .get_mut(&egui::FontFamily::Proportional)
             {
-                // Set the appropriate font weight for the theme.
-                // The best choice was found experimentally.
-                font[0] = match theme {
-                    Theme::Dark => "Ubuntu-Light".to_owned(),
-                    Theme::Light => "Ubuntu-Regular".to_owned(),
-                };
+                family[0] = name.clone();
             }
-            ctx.set_fonts(fonts);
+        } else if let Some(family) = fonts
+            .fonts_for_family
+            .get_mut(&egui::FontFamily::Proportional)
+        {
+            // Set the appropriate font weight for the theme.
+            // The best choice was found experimentally.
+            family[0] = match self.theme.resolved() {
+                Theme::Dark => "Ubuntu-Light".to_owned(),
+                Theme::Light => "Ubuntu-Regular".to_owned(),
+            };
         }
+
+        ctx.set_fonts(fonts);
     }
 }
 
+/// Layer the accent color on top of a base light/dark `Visuals`, so it composes with
+/// either theme instead of replacing the whole palette.
+fn apply_accent(visuals: &mut egui::Visuals, accent: Accent) {
+    visuals.selection.bg_fill = accent.to_color32();
+    visuals.widgets.hovered.bg_fill = accent.lighter();
+    visuals.widgets.active.bg_fill = accent.darker();
+    visuals.hyperlink_color = accent.to_color32();
+}
+
 /// Install embedded fonts.
 fn install_fonts(ctx: &egui::CtxRef) {
     let mut fonts = egui::FontDefinitions::default();
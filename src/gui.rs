@@ -0,0 +1,95 @@
+use crate::framework::AppearanceState;
+use crate::theme::ThemePreference;
+
+/// The application's egui-based UI.
+pub(crate) struct Gui {
+    appearance_open: bool,
+}
+
+impl Gui {
+    pub(crate) fn new() -> Self {
+        Self {
+            appearance_open: false,
+        }
+    }
+
+    /// Draw the UI for one frame, reading and writing appearance settings through
+    /// `appearance` so `Framework` can apply whatever the user changed.
+    pub(crate) fn ui(&mut self, ctx: &egui::CtxRef, appearance: &mut AppearanceState<'_>) {
+        egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                ui.menu_button("View", |ui| {
+                    if ui.button("Appearance...").clicked() {
+                        self.appearance_open = true;
+                        ui.close_menu();
+                    }
+                });
+            });
+        });
+
+        egui::Window::new("Appearance")
+            .open(&mut self.appearance_open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("Theme");
+                egui::ComboBox::from_id_source("theme_preference")
+                    .selected_text(theme_label(appearance.theme_preference))
+                    .show_ui(ui, |ui| {
+                        for preference in [
+                            ThemePreference::System,
+                            ThemePreference::Light,
+                            ThemePreference::Dark,
+                        ] {
+                            let selected = appearance.theme_preference == preference;
+                            if ui
+                                .selectable_label(selected, theme_label(preference))
+                                .clicked()
+                            {
+                                appearance.theme_preference = preference;
+                            }
+                        }
+                    });
+
+                ui.separator();
+
+                ui.label("Font");
+                egui::ComboBox::from_id_source("font_family")
+                    .selected_text(appearance.font_family.as_deref().unwrap_or("Default"))
+                    .show_ui(ui, |ui| {
+                        if ui
+                            .selectable_label(appearance.font_family.is_none(), "Default")
+                            .clicked()
+                        {
+                            appearance.font_family = None;
+                        }
+                        for family in appearance.available_fonts {
+                            let selected = appearance.font_family.as_deref() == Some(family);
+                            if ui.selectable_label(selected, family).clicked() {
+                                appearance.font_family = Some(family.clone());
+                            }
+                        }
+                    });
+
+                ui.separator();
+
+                ui.label("Accent color");
+                ui.add(egui::Slider::new(&mut appearance.accent.hue, 0.0..=360.0).text("Hue"));
+                ui.add(
+                    egui::Slider::new(&mut appearance.accent.saturation, 0.0..=1.0)
+                        .text("Saturation"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut appearance.accent.brightness, 0.0..=1.0)
+                        .text("Brightness"),
+                );
+            });
+    }
+}
+
+fn theme_label(preference: ThemePreference) -> &'static str {
+    match preference {
+        ThemePreference::System => "System",
+        ThemePreference::Light => "Light",
+        ThemePreference::Dark => "Dark",
+    }
+}
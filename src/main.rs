@@ -1,20 +1,21 @@
 #![deny(clippy::all)]
 
-use crate::framework::Framework;
+use crate::framework::{Framework, Redraw};
 use crate::gpu::{Error, Gpu};
 use crate::gui::Gui;
 use log::error;
 use winit::event::{Event, WindowEvent};
 use winit::event_loop::{ControlFlow, EventLoop};
-use winit::window::{Theme, WindowBuilder};
+use winit::window::WindowBuilder;
 use winit_input_helper::WinitInputHelper;
 
-#[cfg(target_os = "windows")]
-use winit::platform::windows::WindowExtWindows;
-
+mod accent;
+mod fonts;
 mod framework;
 mod gpu;
 mod gui;
+mod settings;
+mod theme;
 
 fn main() -> Result<(), Error> {
     env_logger::init();
@@ -28,15 +29,11 @@ fn main() -> Result<(), Error> {
     let (mut gpu, mut framework) = {
         let window_size = window.inner_size();
         let scale_factor = window.scale_factor();
-        let theme = if cfg!(target_os = "windows") {
-            window.theme()
-        } else {
-            Theme::Dark
-        };
+        let settings = settings::load();
 
         let gui = Gui::new();
         let gpu = Gpu::new(&window, window_size)?;
-        let framework = Framework::new(window_size, scale_factor, theme, gui, &gpu);
+        let framework = Framework::new(window_size, scale_factor, settings, gui, &gpu);
 
         (gpu, framework)
     };
@@ -45,6 +42,15 @@ fn main() -> Result<(), Error> {
         // Update egui inputs
         framework.handle_event(&event);
 
+        // Ask for a redraw only for events that can plausibly change what's on
+        // screen. Bare cursor motion is excluded: a static window shouldn't redraw on
+        // every pixel the mouse crosses, only once an actual interaction happens.
+        if let Event::WindowEvent { event, .. } = &event {
+            if wants_redraw(event) {
+                window.request_redraw();
+            }
+        }
+
         if let Event::WindowEvent {
             event: WindowEvent::ThemeChanged(theme),
             ..
@@ -54,10 +60,31 @@ fn main() -> Result<(), Error> {
             window.request_redraw();
         }
 
+        // Handle DPI changes atomically: winit delivers the new size together with
+        // the new scale factor, so resize the surface and rescale egui in lockstep
+        // instead of waiting for two separate WinitInputHelper updates to land.
+        if let Event::WindowEvent {
+            event:
+                WindowEvent::ScaleFactorChanged {
+                    scale_factor,
+                    new_inner_size,
+                },
+            ..
+        } = &event
+        {
+            let size = **new_inner_size;
+            if size.width > 0 && size.height > 0 {
+                gpu.resize(size);
+                framework.resize(size.width, size.height);
+            }
+            framework.set_scale_factor(*scale_factor);
+            window.request_redraw();
+        }
+
         // Draw the current frame
         if let Event::RedrawRequested(_) = event {
             // Prepare egui
-            framework.prepare();
+            let redraw = framework.prepare();
 
             let render_result = gpu
                 .prepare()
@@ -76,6 +103,15 @@ fn main() -> Result<(), Error> {
 
             // Complete frame
             gpu.queue.submit(Some(encoder.finish()));
+
+            // Only keep redrawing while egui has pending work; otherwise go idle.
+            *control_flow = match redraw {
+                Redraw::Immediate => {
+                    window.request_redraw();
+                    ControlFlow::Poll
+                }
+                Redraw::Wait => ControlFlow::Wait,
+            };
         }
 
         // Handle input events
@@ -86,11 +122,6 @@ fn main() -> Result<(), Error> {
                 return;
             }
 
-            // Update the scale factor
-            if let Some(scale_factor) = input.scale_factor() {
-                framework.scale_factor(scale_factor);
-            }
-
             // Resize the window
             if let Some(size) = input.window_resized() {
                 if size.width > 0 && size.height > 0 {
@@ -98,9 +129,15 @@ fn main() -> Result<(), Error> {
                     framework.resize(size.width, size.height);
                 }
             }
-
-            // Update internal state and request a redraw
-            window.request_redraw();
         }
     });
 }
+
+/// Whether a raw window event can plausibly change what egui draws, and so should
+/// wake the idle event loop for a redraw.
+fn wants_redraw(event: &WindowEvent) -> bool {
+    !matches!(
+        event,
+        WindowEvent::CursorMoved { .. } | WindowEvent::AxisMotion { .. } | WindowEvent::Moved(_)
+    )
+}
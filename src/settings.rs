@@ -0,0 +1,61 @@
+use crate::accent::Accent;
+use crate::theme::ThemePreference;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// User preferences persisted across restarts.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Settings {
+    #[serde(default)]
+    pub(crate) theme: ThemePreference,
+    /// System font family chosen in the font picker, if any.
+    #[serde(default)]
+    pub(crate) font_family: Option<String>,
+    #[serde(default)]
+    pub(crate) accent: Accent,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: ThemePreference::System,
+            font_family: None,
+            accent: Accent::default(),
+        }
+    }
+}
+
+/// Load the persisted settings, falling back to defaults if none exist yet or the
+/// file can't be parsed.
+pub(crate) fn load() -> Settings {
+    config_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+pub(crate) fn save(settings: &Settings) {
+    let Some(path) = config_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        if let Err(e) = fs::create_dir_all(parent) {
+            log::warn!("failed to create config dir: {}", e);
+            return;
+        }
+    }
+    match serde_json::to_string_pretty(settings) {
+        Ok(json) => {
+            if let Err(e) = fs::write(path, json) {
+                log::warn!("failed to persist settings: {}", e);
+            }
+        }
+        Err(e) => log::warn!("failed to serialize settings: {}", e),
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    directories::ProjectDirs::from("", "", "CarTunes")
+        .map(|dirs| dirs.config_dir().join("settings.json"))
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use winit::window::Theme;
+
+/// The user's theme preference, as stored in the persisted settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum ThemePreference {
+    /// Follow the OS light/dark setting.
+    System,
+    Light,
+    Dark,
+}
+
+impl Default for ThemePreference {
+    fn default() -> Self {
+        ThemePreference::System
+    }
+}
+
+/// Owns the user's theme preference and the currently resolved `winit::window::Theme`,
+/// consolidating logic that used to be split between `main` and `Framework`.
+pub(crate) struct ThemeController {
+    preference: ThemePreference,
+    resolved: Theme,
+    dirty: bool,
+}
+
+impl ThemeController {
+    /// Resolve the given preference (e.g. loaded from persisted settings) to a
+    /// concrete theme.
+    pub(crate) fn new(preference: ThemePreference) -> Self {
+        let resolved = resolve(preference);
+
+        Self {
+            preference,
+            resolved,
+            dirty: true,
+        }
+    }
+
+    /// The currently resolved theme, used to style egui.
+    pub(crate) fn resolved(&self) -> Theme {
+        self.resolved
+    }
+
+    /// Set the user's preference, re-resolving immediately. The caller is
+    /// responsible for persisting the new preference.
+    pub(crate) fn set_preference(&mut self, preference: ThemePreference) {
+        self.preference = preference;
+        self.resolved = resolve(preference);
+        self.dirty = true;
+    }
+
+    pub(crate) fn preference(&self) -> ThemePreference {
+        self.preference
+    }
+
+    /// Adopt the OS theme reported by winit. Only has an effect when following
+    /// `System`; called when winit reports `WindowEvent::ThemeChanged`.
+    pub(crate) fn refresh_system(&mut self, os_theme: Theme) {
+        if self.preference == ThemePreference::System && self.resolved != os_theme {
+            self.resolved = os_theme;
+            self.dirty = true;
+        }
+    }
+
+    /// Returns `true` exactly once after the resolved theme changes, so the caller
+    /// knows to restyle the egui context.
+    pub(crate) fn take_dirty(&mut self) -> bool {
+        std::mem::take(&mut self.dirty)
+    }
+}
+
+/// Detect the OS light/dark setting on the current platform, falling back to `Dark`
+/// when detection isn't available (e.g. unsupported desktop environment).
+fn detect_system_theme() -> Theme {
+    match dark_light::detect() {
+        dark_light::Mode::Light => Theme::Light,
+        dark_light::Mode::Dark | dark_light::Mode::Default => Theme::Dark,
+    }
+}
+
+fn resolve(preference: ThemePreference) -> Theme {
+    match preference {
+        ThemePreference::System => detect_system_theme(),
+        ThemePreference::Light => Theme::Light,
+        ThemePreference::Dark => Theme::Dark,
+    }
+}